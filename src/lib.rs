@@ -1,4 +1,22 @@
+use std::cmp::Ordering;
 use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum System {
+    Binary,
+    Decimal,
+}
+
+impl System {
+    fn base(&self) -> f64 {
+        match self {
+            System::Binary => 1024.0,
+            System::Decimal => 1000.0,
+        }
+    }
+}
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum Unit {
@@ -7,6 +25,7 @@ pub enum Unit {
     Mega,
     Giga,
     Tera,
+    Peta,
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -16,6 +35,7 @@ pub enum UnitValue {
     Mega = 1 << 20,
     Giga = 1 << 30,
     Tera = 1 << 40,
+    Peta = 1 << 50,
 }
 
 impl Unit {
@@ -26,73 +46,296 @@ impl Unit {
             Unit::Mega => UnitValue::Mega,
             Unit::Giga => UnitValue::Giga,
             Unit::Tera => UnitValue::Tera,
+            Unit::Peta => UnitValue::Peta,
+        }
+    }
+
+    fn power(&self) -> i32 {
+        match self {
+            Unit::Byte => 0,
+            Unit::Kilo => 1,
+            Unit::Mega => 2,
+            Unit::Giga => 3,
+            Unit::Tera => 4,
+            Unit::Peta => 5,
+        }
+    }
+
+    fn factor(&self, system: System) -> f64 {
+        match system {
+            System::Binary => self.to_value() as u64 as f64,
+            System::Decimal => system.base().powi(self.power()),
+        }
+    }
+
+    fn prefix(&self, system: System) -> &'static str {
+        match (system, self) {
+            (_, Unit::Byte) => "",
+            (System::Binary, Unit::Kilo) => "Ki",
+            (System::Binary, Unit::Mega) => "Mi",
+            (System::Binary, Unit::Giga) => "Gi",
+            (System::Binary, Unit::Tera) => "Ti",
+            (System::Binary, Unit::Peta) => "Pi",
+            (System::Decimal, Unit::Kilo) => "K",
+            (System::Decimal, Unit::Mega) => "M",
+            (System::Decimal, Unit::Giga) => "G",
+            (System::Decimal, Unit::Tera) => "T",
+            (System::Decimal, Unit::Peta) => "P",
         }
     }
 }
 
 impl fmt::Display for Unit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let string = match self {
-            Unit::Byte => "",
-            Unit::Kilo => "Ki",
-            Unit::Mega => "Mi",
-            Unit::Giga => "Gi",
-            Unit::Tera => "Ti"
-        }.to_string();
-
-        write!(f, "{}", string)
+        write!(f, "{}", self.prefix(System::Binary))
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct Amount {
     bytes: f64,
-    unit: Unit
+    unit: Unit,
+    system: System,
 }
 
 impl Amount {
     pub fn new(bytes: f64, unit: Unit) -> Amount {
-        Amount { bytes, unit }
+        Amount { bytes, unit, system: System::Binary }
     }
 
     pub fn auto_detect(bytes: f64) -> Amount {
-        let scales: [Unit; 5] = [Unit::Byte, Unit::Kilo, Unit::Mega, Unit::Giga, Unit::Tera];
+        Self::auto_detect_with(bytes, System::Binary)
+    }
+
+    pub fn auto_detect_with(bytes: f64, system: System) -> Amount {
+        const SCALES: [Unit; 6] = [
+            Unit::Byte, Unit::Kilo, Unit::Mega, Unit::Giga, Unit::Tera, Unit::Peta,
+        ];
         let mut amount = bytes;
         let mut counter = 0;
 
         if amount <= 0_f64 {
-            return Self::new(0_f64, Unit::Byte)
+            return Amount { bytes: 0_f64, unit: Unit::Byte, system }
         }
 
-        while amount > 1.0 && counter < 5 {
-            amount = amount / 1024.0;
+        while amount >= system.base() && counter < SCALES.len() - 1 {
+            amount /= system.base();
             counter += 1
         }
 
-        Self::new(bytes, scales[counter - 1])
+        Amount { bytes, unit: SCALES[counter], system }
+    }
+
+    pub fn with_system(self, system: System) -> Amount {
+        Self::auto_detect_with(self.bytes, system)
+    }
+
+    pub fn from_kilos(kilos: f64) -> Amount {
+        Self::auto_detect(kilos * Unit::Kilo.factor(System::Binary))
+    }
+
+    pub fn from_megas(megas: f64) -> Amount {
+        Self::auto_detect(megas * Unit::Mega.factor(System::Binary))
+    }
+
+    pub fn from_gigas(gigas: f64) -> Amount {
+        Self::auto_detect(gigas * Unit::Giga.factor(System::Binary))
+    }
+
+    pub fn from_teras(teras: f64) -> Amount {
+        Self::auto_detect(teras * Unit::Tera.factor(System::Binary))
+    }
+
+    pub fn as_kilos(&self) -> f64 {
+        self.bytes / Unit::Kilo.factor(self.system)
+    }
+
+    pub fn as_megas(&self) -> f64 {
+        self.bytes / Unit::Mega.factor(self.system)
+    }
+
+    pub fn as_gigas(&self) -> f64 {
+        self.bytes / Unit::Giga.factor(self.system)
+    }
+
+    pub fn as_teras(&self) -> f64 {
+        self.bytes / Unit::Tera.factor(self.system)
     }
 
     pub fn quantity(&self) -> f64 {
-        self.bytes / (self.unit.to_value() as u64 as f64)
+        self.bytes / self.unit.factor(self.system)
     }
 
     pub fn unit(&self) -> Unit {
         self.unit
     }
 
+    pub fn system(&self) -> System {
+        self.system
+    }
+
     pub fn bytes(&self) -> f64 {
         self.bytes
     }
+
+    pub fn format(&self) -> AmountFormatter {
+        AmountFormatter::new(*self)
+    }
+}
+
+pub struct AmountFormatter {
+    amount: Amount,
+    precision: usize,
+    space: bool,
+    grouped: bool,
+}
+
+impl AmountFormatter {
+    fn new(amount: Amount) -> AmountFormatter {
+        AmountFormatter { amount, precision: 1, space: true, grouped: false }
+    }
+
+    pub fn precision(mut self, precision: usize) -> AmountFormatter {
+        self.precision = precision;
+        self
+    }
+
+    pub fn space(mut self, space: bool) -> AmountFormatter {
+        self.space = space;
+        self
+    }
+
+    pub fn grouped(mut self, grouped: bool) -> AmountFormatter {
+        self.grouped = grouped;
+        self
+    }
+
+    pub fn build(&self) -> String {
+        if self.grouped {
+            return group_digits(self.amount.bytes.round() as u64)
+        }
+
+        let separator = if self.space { " " } else { "" };
+
+        format!(
+            "{:.*}{}{}B",
+            self.precision,
+            self.amount.quantity(),
+            separator,
+            self.amount.unit.prefix(self.amount.system),
+        )
+    }
+}
+
+fn group_digits(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::new();
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    grouped
 }
 
 impl fmt::Display for Amount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:.1} {}B", self.quantity(), self.unit.to_string())
+        let precision = f.precision().unwrap_or(1);
+        write!(f, "{:.*} {}B", precision, self.quantity(), self.unit.prefix(self.system))
+    }
+}
+
+fn factor_from_suffix(suffix: &str) -> Result<f64, String> {
+    let normalized = suffix.trim().to_lowercase();
+    let core = normalized.strip_suffix('b').unwrap_or(&normalized);
+    let binary = core.ends_with('i');
+    let letter = core.strip_suffix('i').unwrap_or(core);
+
+    let base: f64 = if binary { 1024.0 } else { 1000.0 };
+    let power = match letter {
+        "" => return Ok(1.0),
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        _ => return Err(format!("couldn't parse {:?} into a known unit", suffix)),
+    };
+
+    Ok(base.powi(power))
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Amount, Self::Err> {
+        let number: String = s.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+        let suffix = &s[number.len()..];
+
+        let value: f64 = number.parse()
+            .map_err(|_| format!("couldn't parse {:?} into a number", number))?;
+
+        Ok(Amount::auto_detect(value * factor_from_suffix(suffix)?))
+    }
+}
+
+impl Add for Amount {
+    type Output = Amount;
+
+    fn add(self, other: Amount) -> Amount {
+        Amount::auto_detect_with(self.bytes + other.bytes, self.system)
+    }
+}
+
+impl Sub for Amount {
+    type Output = Amount;
+
+    fn sub(self, other: Amount) -> Amount {
+        Amount::auto_detect_with(self.bytes - other.bytes, self.system)
+    }
+}
+
+impl Mul<f64> for Amount {
+    type Output = Amount;
+
+    fn mul(self, scalar: f64) -> Amount {
+        Amount::auto_detect_with(self.bytes * scalar, self.system)
+    }
+}
+
+impl Div<f64> for Amount {
+    type Output = Amount;
+
+    fn div(self, scalar: f64) -> Amount {
+        Amount::auto_detect_with(self.bytes / scalar, self.system)
+    }
+}
+
+impl PartialEq for Amount {
+    fn eq(&self, other: &Amount) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for Amount {}
+
+impl PartialOrd for Amount {
+    fn partial_cmp(&self, other: &Amount) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Amount {
+    fn cmp(&self, other: &Amount) -> Ordering {
+        self.bytes.total_cmp(&other.bytes)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Unit, UnitValue, Amount};
+    use super::{Unit, UnitValue, Amount, System};
 
     #[test]
     fn unit_to_value() {
@@ -101,6 +344,7 @@ mod test {
         assert_eq!(Unit::Mega.to_value(), UnitValue::Mega);
         assert_eq!(Unit::Giga.to_value(), UnitValue::Giga);
         assert_eq!(Unit::Tera.to_value(), UnitValue::Tera);
+        assert_eq!(Unit::Peta.to_value(), UnitValue::Peta);
     }
 
     #[test]
@@ -110,6 +354,7 @@ mod test {
         assert_eq!(format!("{}", Unit::Mega), String::from("Mi"));
         assert_eq!(format!("{}", Unit::Giga), String::from("Gi"));
         assert_eq!(format!("{}", Unit::Tera), String::from("Ti"));
+        assert_eq!(format!("{}", Unit::Peta), String::from("Pi"));
     }
 
     #[test]
@@ -150,6 +395,8 @@ mod test {
         assert_eq!(Amount::auto_detect(1234567.0).unit, Unit::Mega);
         assert_eq!(Amount::auto_detect(1234567890.0).unit, Unit::Giga);
         assert_eq!(Amount::auto_detect(1234567890123.0).unit, Unit::Tera);
+        assert_eq!(Amount::auto_detect(5.0 * (1u64 << 50) as f64).unit, Unit::Peta);
+        assert_eq!(Amount::auto_detect(f64::MAX).unit, Unit::Peta);
     }
 
     #[test]
@@ -157,4 +404,106 @@ mod test {
         assert_eq!(format!("{}", Amount::auto_detect(42.0)), "42.0 B");
         assert_eq!(format!("{}", Amount::auto_detect(200124.42)), "195.4 KiB");
     }
+
+    #[test]
+    fn amount_auto_detect_with_decimal() {
+        assert_eq!(Amount::auto_detect_with(2000.0, System::Decimal).unit(), Unit::Kilo);
+        assert_eq!(Amount::auto_detect_with(1234567.0, System::Decimal).unit(), Unit::Mega);
+        assert_eq!(Amount::auto_detect_with(1234567890.0, System::Decimal).unit(), Unit::Giga);
+    }
+
+    #[test]
+    fn amount_display_decimal() {
+        assert_eq!(format!("{}", Amount::auto_detect_with(2000.0, System::Decimal)), "2.0 KB");
+        assert_eq!(format!("{}", Amount::auto_detect_with(2_000_000.0, System::Decimal)), "2.0 MB");
+    }
+
+    #[test]
+    fn amount_with_system() {
+        let amount = Amount::auto_detect(1500.0).with_system(System::Decimal);
+
+        assert_eq!(amount.system(), System::Decimal);
+        assert_eq!(amount.unit(), Unit::Kilo);
+        assert_eq!(format!("{}", amount), "1.5 KB");
+    }
+
+    #[test]
+    fn amount_arithmetic() {
+        let sum = Amount::from_kilos(1.0) + Amount::from_kilos(3.0);
+        assert_eq!(sum.bytes(), 4096.0);
+        assert_eq!(sum.unit(), Unit::Kilo);
+
+        let diff = Amount::from_megas(2.0) - Amount::from_megas(1.0);
+        assert_eq!(diff.bytes(), 1024.0 * 1024.0);
+        assert_eq!(diff.unit(), Unit::Mega);
+    }
+
+    #[test]
+    fn amount_scalar() {
+        assert_eq!((Amount::from_kilos(2.0) * 4.0).as_kilos(), 8.0);
+        assert_eq!((Amount::from_gigas(1.0) / 2.0).as_megas(), 512.0);
+    }
+
+    #[test]
+    fn amount_ordering() {
+        assert!(Amount::from_kilos(1.0) < Amount::from_megas(1.0));
+        assert_eq!(Amount::from_kilos(1.0), Amount::new(1024.0, Unit::Byte));
+
+        let mut sizes = [Amount::from_megas(1.0), Amount::from_kilos(1.0), Amount::from_gigas(1.0)];
+        sizes.sort();
+        assert_eq!(sizes[0].unit(), Unit::Kilo);
+        assert_eq!(sizes[2].unit(), Unit::Giga);
+    }
+
+    #[test]
+    fn amount_accessors() {
+        let amount = Amount::from_gigas(1.0);
+
+        assert_eq!(amount.as_kilos(), 1024.0 * 1024.0);
+        assert_eq!(amount.as_megas(), 1024.0);
+        assert_eq!(amount.as_gigas(), 1.0);
+    }
+
+    #[test]
+    fn amount_format_precision() {
+        let amount = Amount::auto_detect(200124.42);
+
+        assert_eq!(amount.format().precision(2).build(), "195.43 KiB");
+        assert_eq!(amount.format().precision(0).space(false).build(), "195KiB");
+    }
+
+    #[test]
+    fn amount_format_honors_formatter_precision() {
+        let amount = Amount::auto_detect(200124.42);
+
+        assert_eq!(format!("{}", amount), "195.4 KiB");
+        assert_eq!(format!("{:.3}", amount), "195.434 KiB");
+    }
+
+    #[test]
+    fn amount_format_grouped() {
+        let amount = Amount::auto_detect(1234567.0);
+
+        assert_eq!(amount.format().grouped(true).build(), "1,234,567");
+        assert_eq!(Amount::auto_detect(42.0).format().grouped(true).build(), "42");
+    }
+
+    #[test]
+    fn amount_from_str() {
+        assert_eq!("42".parse::<Amount>().unwrap().bytes(), 42.0);
+        assert_eq!("200 KB".parse::<Amount>().unwrap().bytes(), 200_000.0);
+        assert_eq!("1.5GiB".parse::<Amount>().unwrap().bytes(), 1.5 * 1024.0 * 1024.0 * 1024.0);
+        assert_eq!("1KiB".parse::<Amount>().unwrap().bytes(), 1024.0);
+        assert_eq!("1KB".parse::<Amount>().unwrap().bytes(), 1000.0);
+        assert_eq!("1024Ti".parse::<Amount>().unwrap().bytes(), 1024.0 * (1u64 << 40) as f64);
+    }
+
+    #[test]
+    fn amount_from_str_errors() {
+        assert_eq!(
+            "1Qb".parse::<Amount>().unwrap_err(),
+            "couldn't parse \"Qb\" into a known unit"
+        );
+        assert!("".parse::<Amount>().is_err());
+    }
 }